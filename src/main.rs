@@ -2,24 +2,68 @@ mod sudoku;
 
 #[macro_use]
 extern crate clap;
-use sudoku::{Board, backtrack};
+use sudoku::{Board, backtrack, count_solutions, diagonal_units, hyper_units};
 use clap::App;
 use std::fs::File;
 use std::io::{BufReader, BufRead};
 
+/// A puzzle is only considered well-formed if it has exactly one
+/// solution, so `--check` only needs to tell ambiguous puzzles
+/// apart from unique ones, not count every solution.
+const UNIQUENESS_CHECK_LIMIT: usize = 2;
 
 fn main() {
     let yaml = load_yaml!("cli.yml");
     let matches = App::from_yaml(yaml).get_matches();
 
     let input_path = matches.value_of("input").unwrap();
+    let check = matches.is_present("check");
+    let variant = matches.value_of("variant");
+    let line_output = matches.is_present("line_output");
+    let order = matches.value_of("order").unwrap_or("3");
 
+    match order {
+        "2" => run::<2, 4>(input_path, check, variant, line_output),
+        "3" => run::<3, 9>(input_path, check, variant, line_output),
+        "4" => run::<4, 16>(input_path, check, variant, line_output),
+        "5" => run::<5, 25>(input_path, check, variant, line_output),
+        _ => unreachable!("clap restricts --order to 2, 3, 4 or 5"),
+    }
+}
+
+/// Solves (or checks) every puzzle in `input_path` as a `Board<ORDER,
+/// N>`, applying `variant`'s extra units first if one was given.
+fn run<const ORDER: usize, const N: usize>(
+    input_path: &str,
+    check: bool,
+    variant: Option<&str>,
+    line_output: bool,
+) {
     if let Ok(file) = File::open(input_path) {
         let mut lines = BufReader::new(file).lines();
-        while let Ok((board, rest_lines)) = Board::parse_puzzle(lines) {
+        while let Ok((board, rest_lines)) = Board::<ORDER, N>::parse_puzzle(lines) {
             lines = rest_lines;
-            if let Some(solved) = backtrack(board, 0, 0) {
-                println!("{}", solved.to_string());
+            let board = match variant {
+                Some("diagonal") => board.with_extra_units(diagonal_units::<N>()),
+                Some("hyper") if N == 9 => board.with_extra_units(hyper_units()),
+                Some("hyper") => {
+                    println!("The hyper variant is only defined for 9x9 boards.");
+                    return;
+                },
+                _ => board,
+            };
+            if check {
+                match count_solutions(board, UNIQUENESS_CHECK_LIMIT) {
+                    0 => println!("no solution"),
+                    1 => println!("unique"),
+                    _ => println!("multiple solutions"),
+                }
+            } else if let Some(solved) = backtrack(board) {
+                if line_output {
+                    println!("{}", solved.to_line_string());
+                } else {
+                    println!("{}", solved.to_string());
+                }
             } else {
                 println!("Could not solve.");
             }
@@ -1,5 +1,6 @@
 use std::char::from_digit;
 use std::io::{BufRead, Lines};
+use std::rc::Rc;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 /// Represents a single cell on the board.
@@ -26,127 +27,320 @@ impl ToString for Cell {
     }
 }
 
-/// Used to store the puzzle's state
-pub struct Board {
-    grid: [[Cell; 9]; 9]
+/// A set of coordinates that must all hold distinct values. The
+/// standard rows, columns and boxes are units, but so is anything
+/// else a variant wants enforced the same way: the two diagonals
+/// of an X-Sudoku, one of hyper-sudoku's four extra windows, or a
+/// killer cage.
+pub type Unit = Vec<(usize, usize)>;
+
+/// Used to store the puzzle's state.
+///
+/// `ORDER` is the box order (3 for a standard sudoku, whose boxes
+/// are 3x3), and `N` is the side length of the board, which must
+/// equal `ORDER * ORDER` (9 for a standard sudoku, 4 for a
+/// quick/mini sudoku, 16 for a hexadoku, 25 for a 5x5-box giant).
+/// `N` can't currently be derived from `ORDER` alone on stable
+/// Rust, so callers must spell both out, e.g. `Board::<3, 9>`.
+pub struct Board<const ORDER: usize, const N: usize> {
+    grid: [[Cell; N]; N],
+    units: Rc<Vec<Unit>>,
+    /// For each cell (indexed `y * N + x`), the indices into `units`
+    /// of the units that contain it. Precomputed whenever `units`
+    /// changes so `get_possible` never has to scan every unit
+    /// looking for the ones that matter.
+    cell_units: Rc<Vec<Vec<usize>>>,
 }
 
-impl Board {
+impl<const ORDER: usize, const N: usize> Board<ORDER, N> {
+    /// Builds the standard units every board is constrained by: its
+    /// `N` rows, `N` columns, and `N` boxes.
+    fn standard_units() -> Vec<Unit> {
+        let mut units: Vec<Unit> = Vec::with_capacity(N * 3);
+
+        // Rows
+        for y in 0..N {
+            units.push((0..N).map(|x| (x, y)).collect());
+        }
+
+        // Columns
+        for x in 0..N {
+            units.push((0..N).map(|y| (x, y)).collect());
+        }
+
+        // Boxes
+        for box_y in 0..ORDER {
+            for box_x in 0..ORDER {
+                let mut unit = Vec::with_capacity(N);
+                for y in 0..ORDER {
+                    for x in 0..ORDER {
+                        unit.push((x + box_x * ORDER, y + box_y * ORDER));
+                    }
+                }
+                units.push(unit);
+            }
+        }
+
+        units
+    }
+
+    /// Builds the `cell_units` index for a given set of `units`: for
+    /// every cell, the indices of the units it belongs to.
+    fn index_units(units: &[Unit]) -> Vec<Vec<usize>> {
+        let mut cell_units = vec![Vec::new(); N * N];
+
+        for (i, unit) in units.iter().enumerate() {
+            for &(x, y) in unit.iter() {
+                cell_units[y * N + x].push(i);
+            }
+        }
+
+        cell_units
+    }
+
+    /// Returns a board identical to this one but with `extra_units`
+    /// appended to its constraint units, e.g. the two diagonals of
+    /// an X-Sudoku. The solver needs no further changes to support
+    /// variants built this way.
+    pub fn with_extra_units(mut self, extra_units: Vec<Unit>) -> Self {
+        let mut units = (*self.units).clone();
+        units.extend(extra_units);
+        self.cell_units = Rc::new(Self::index_units(&units));
+        self.units = Rc::new(units);
+        self
+    }
+
     /// Creates a clone of a board, replacing a single cell with a
     /// new one.
     fn patch(&self, x: usize, y: usize, cell: Cell) -> Self {
         let mut grid = self.grid.clone();
 
         grid[y][x] = cell;
-        
+
         Board {
             grid: grid,
+            units: self.units.clone(),
+            cell_units: self.cell_units.clone(),
         }
     }
 
     /// Gets all possible numbers for a cell, taking into account
-    /// the row, column, and box the cell resides in.
+    /// every unit (row, column, box, or variant-specific) it
+    /// belongs to.
     fn get_possible(&self, x: usize, y: usize) -> Vec<u8> {
-        let mut nums: Vec<bool> = vec![true; 9];
-        
-        // Search across a row
-        for x in 0..9 {
-            match self.grid[y][x] {
-                Cell::Fixed(num) | Cell::Maybe(num) => {
-                    nums[(num - 1) as usize] = false;
-                },
-                Cell::Open => continue,
-            }
-        }
-
-        // Search across a column
-        for y in 0..9 {
-            match self.grid[y][x] {
-                Cell::Fixed(num) | Cell::Maybe(num) => {
-                    nums[(num - 1) as usize] = false;
-                },
-                Cell::Open => continue,
-            }
-        }
+        let mut nums: Vec<bool> = vec![true; N];
 
-        // Search across a box
-        let grid_x = x / 3;
-        let grid_y = y / 3;
-        for y in 0 .. 3 {
-            for x in 0 .. 3 {
-                match self.grid[y + (grid_y * 3)][x + (grid_x * 3)] {
+        for &i in self.cell_units[y * N + x].iter() {
+            for &(ux, uy) in self.units[i].iter() {
+                match self.grid[uy][ux] {
                     Cell::Fixed(num) | Cell::Maybe(num) => {
                         nums[(num - 1) as usize] = false;
                     },
                     Cell::Open => continue,
-                }      
+                }
             }
         }
 
-        let mut ret_nums: Vec<u8> = Vec::with_capacity(9);
+        let mut ret_nums: Vec<u8> = Vec::with_capacity(N);
         let mut n = 1;
         for num in nums.into_iter() {
             if num {
                 ret_nums.push(n);
             }
-            
+
             n += 1;
         }
 
         ret_nums
     }
 
-    /// Parses a puzzle from an input source
-    /// This is expected to be ascii-encoded,
-    /// representing empty cells with spaces
-    pub fn parse_puzzle<B: BufRead>(mut lines: Lines<B>) -> Result<(Self, Lines<B>), &'static str> {
-        let mut grid = [[Cell::Open; 9]; 9];
-
-        for y in 0..9 {
-            let mut x = 0;
-            if let Some(Ok(line)) = lines.next() {
-                for chr in line.chars().into_iter().take(9) {
-                    grid[y][x] = match chr {
-                        chr @ '1' ... '9' => {
-                            Cell::Fixed(chr as u32 as u8 - 48)
-                        },
-                        _ => {
-                            Cell::Open
-                        },
-                    };            
-                    x = x + 1;
+    /// Checks whether any unit already holds two filled cells with
+    /// the same value, e.g. two identical given digits in the same
+    /// row/column/box. Naked and hidden singles only ever fix a cell
+    /// to a value its peers have ruled out, so they can't create or
+    /// detect this; it can only come from the puzzle's own givens, so
+    /// a single pass over the units is enough to catch it up front
+    /// instead of leaving the search to fail to discover it.
+    fn has_conflict(&self) -> bool {
+        for unit in self.units.iter() {
+            let mut seen = vec![false; N];
+            for &(x, y) in unit.iter() {
+                if let Cell::Fixed(num) | Cell::Maybe(num) = self.grid[y][x] {
+                    let idx = (num - 1) as usize;
+                    if seen[idx] {
+                        return true;
+                    }
+                    seen[idx] = true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Looks across every unit for a value that can only go in a
+    /// single open cell, returning its coordinates and the value if
+    /// one is found.
+    fn find_hidden_single(&self) -> Option<(usize, usize, u8)> {
+        for unit in self.units.iter() {
+            if let Some(found) = self.find_hidden_single_in(unit) {
+                return Some(found);
+            }
+        }
+
+        None
+    }
+
+    /// Within a single unit (row, column or box), finds a value
+    /// that appears as a candidate of exactly one open cell.
+    fn find_hidden_single_in(&self, unit: &[(usize, usize)]) -> Option<(usize, usize, u8)> {
+        for num in 1..(N as u8 + 1) {
+            let mut found = None;
+            let mut count = 0;
+
+            for &(x, y) in unit.iter() {
+                if let Cell::Open = self.grid[y][x] {
+                    if self.get_possible(x, y).contains(&num) {
+                        count += 1;
+                        found = Some((x, y));
+                    }
+                }
+            }
+
+            if count == 1 {
+                let (x, y) = found.unwrap();
+                return Some((x, y, num));
+            }
+        }
+
+        None
+    }
+
+    /// Picks the open cell with the fewest remaining candidates
+    /// (minimum-remaining-values), or `None` if the board is full.
+    fn select_cell(&self) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize, usize)> = None;
+
+        for y in 0..N {
+            for x in 0..N {
+                if let Cell::Open = self.grid[y][x] {
+                    let count = self.get_possible(x, y).len();
+                    if best.is_none_or(|(_, _, best_count)| count < best_count) {
+                        best = Some((x, y, count));
+                    }
                 }
-            } else {
-                return Err("Reached end of input.");
             }
         }
 
-        // Read a blank line
-        lines.next();
+        best.map(|(x, y, _)| (x, y))
+    }
 
-        let board = Board { grid: grid };
+    /// Parses a puzzle from an input source.
+    /// This accepts the traditional N-line block form (one row per
+    /// line, followed by a blank separator line) as well as the
+    /// compact single-line form used by many puzzle databases,
+    /// where one puzzle is `N*N` consecutive characters on a single
+    /// line with no separators; a line is treated as the compact
+    /// form whenever it's exactly `N*N` characters long. Empty cells
+    /// may be written as a space, `'.'`, `'0'`, or `'_'`. Fixed cells
+    /// are base-36 digits (`'1'..'9'` for a standard board, extending
+    /// into `'A'..'Z'` for boards wider than 9 cells, e.g. `'1'..'9'`
+    /// then `'A'..'G'` for a 16x16 hexadoku).
+    pub fn parse_puzzle<B: BufRead>(mut lines: Lines<B>) -> Result<(Self, Lines<B>), &'static str> {
+        let mut grid = [[Cell::Open; N]; N];
+
+        let first_line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return Err("Reached end of input."),
+        };
+
+        if first_line.chars().count() == N * N {
+            let chars: Vec<char> = first_line.chars().collect();
+            for y in 0..N {
+                for x in 0..N {
+                    grid[y][x] = Self::decode_cell(chars[y * N + x]);
+                }
+            }
+        } else {
+            Self::parse_row(&first_line, &mut grid[0]);
+            for row in grid.iter_mut().skip(1) {
+                if let Some(Ok(line)) = lines.next() {
+                    Self::parse_row(&line, row);
+                } else {
+                    return Err("Reached end of input.");
+                }
+            }
+
+            // Read a blank line
+            lines.next();
+        }
+
+        let units = Self::standard_units();
+        let cell_units = Self::index_units(&units);
+        let board = Board {
+            grid: grid,
+            units: Rc::new(units),
+            cell_units: Rc::new(cell_units),
+        };
         Ok((board, lines))
     }
+
+    /// Decodes a single row of the block input form into `row`.
+    fn parse_row(line: &str, row: &mut [Cell; N]) {
+        for (x, chr) in line.chars().take(N).enumerate() {
+            row[x] = Self::decode_cell(chr);
+        }
+    }
+
+    /// Decodes a single input character into a cell: a base-36
+    /// digit in `1..=N` is a fixed cell, anything else (a space,
+    /// `'.'`, `'0'`, `'_'`, ...) is an open one.
+    fn decode_cell(chr: char) -> Cell {
+        let value = chr.to_digit(36).unwrap_or(0) as usize;
+        if value >= 1 && value <= N {
+            Cell::Fixed(value as u8)
+        } else {
+            Cell::Open
+        }
+    }
+
+    /// Encodes a single cell the same way `to_string` and
+    /// `to_line_string` both do: a base-36 digit for a set cell,
+    /// a space for an open one.
+    fn encode_cell(cell: Cell) -> char {
+        match cell {
+            Cell::Fixed(num) | Cell::Maybe(num) => {
+                from_digit(num as u32, 36).unwrap().to_ascii_uppercase()
+            },
+            Cell::Open => ' ',
+        }
+    }
+
+    /// Serializes the board into the compact single-line form (`N*N`
+    /// characters, row after row, with no separators) that
+    /// `parse_puzzle` auto-detects, so puzzles round-trip through
+    /// that format too.
+    pub fn to_line_string(&self) -> String {
+        let mut board_str = String::with_capacity(N * N);
+
+        for row in self.grid.iter() {
+            for cell in row.iter() {
+                board_str.push(Self::encode_cell(*cell));
+            }
+        }
+
+        board_str
+    }
 }
 
 
-impl ToString for Board {
+impl<const ORDER: usize, const N: usize> ToString for Board<ORDER, N> {
     fn to_string(&self) -> String {
         let mut board_str = String::new();
 
         for row in self.grid.iter() {
             for cell in row.iter() {
-                board_str.push(
-                    match *cell {
-                        Cell::Fixed(num) => {
-                            from_digit(num as u32, 10).unwrap()
-                        },
-                        Cell::Maybe(num) => {
-                            from_digit(num as u32, 10).unwrap()
-                        },
-                        Cell::Open => ' ',
-                    }
-                );
+                board_str.push(Self::encode_cell(*cell));
             }
             board_str.push('\n');
         }
@@ -157,46 +351,140 @@ impl ToString for Board {
 
 
 /// Attempts to solve a sudoku board with backtrack
-/// brute-forcing.
-pub fn backtrack(board: Board, x: usize, y: usize) -> Option<Board> {
-    let (next_x, next_y) = next_coords(x, y);
-    if y == 9 {
-        return Some(board);
+/// brute-forcing, propagating naked and hidden singles
+/// before (and between) guesses, and always branching on the open
+/// cell with the fewest remaining candidates (minimum-remaining-
+/// values) to cut down the search tree.
+pub fn backtrack<const ORDER: usize, const N: usize>(board: Board<ORDER, N>) -> Option<Board<ORDER, N>> {
+    let board = match propagate(board) {
+        Some(board) => board,
+        None => return None,
+    };
+
+    let (x, y) = match board.select_cell() {
+        Some(coords) => coords,
+        None => return Some(board),
+    };
+
+    let nums = board.get_possible(x, y);
+
+    for num in nums.iter() {
+        if let Some(solved) = backtrack(board.patch(x, y, Cell::Maybe(*num))) {
+            return Some(solved);
+        }
     }
-    match board.grid[y][x] {
-        Cell::Fixed(_) | Cell::Maybe(_) => {
-            backtrack(board, next_x, next_y)
+
+    None
+}
+
+/// Counts how many distinct solutions a board has, stopping as soon
+/// as `limit` is reached. A well-formed puzzle should have exactly
+/// one; this lets a caller tell a unique puzzle apart from one that's
+/// under-constrained (multiple solutions) or over-constrained (none)
+/// without paying for an exhaustive search on an ambiguous board.
+pub fn count_solutions<const ORDER: usize, const N: usize>(board: Board<ORDER, N>, limit: usize) -> usize {
+    let mut count = 0;
+    count_solutions_rec(board, limit, &mut count);
+    count
+}
+
+fn count_solutions_rec<const ORDER: usize, const N: usize>(board: Board<ORDER, N>, limit: usize, count: &mut usize) {
+    if *count >= limit {
+        return;
+    }
+
+    let board = match propagate(board) {
+        Some(board) => board,
+        None => return,
+    };
+
+    let (x, y) = match board.select_cell() {
+        Some(coords) => coords,
+        None => {
+            *count += 1;
+            return;
         },
-        Cell::Open => {
-            let nums = board.get_possible(x, y);
-            
-            for num in nums.iter() {
-                if let Some(solved) = backtrack(board.patch(x, y, Cell::Maybe(*num)), next_x, next_y) {
-                    return Some(solved);
+    };
+
+    for num in board.get_possible(x, y) {
+        if *count >= limit {
+            return;
+        }
+        count_solutions_rec(board.patch(x, y, Cell::Maybe(num)), limit, count);
+    }
+}
+
+/// Repeatedly fixes naked singles (an open cell with exactly one
+/// remaining candidate) and hidden singles (a value that can only
+/// go in one cell within some row, column or box), until neither
+/// rule makes further progress. Returns `None` as soon as an open
+/// cell is left with no candidates at all, or as soon as two of the
+/// board's own givens already clash in some unit, since either way
+/// the board can no longer be solved.
+fn propagate<const ORDER: usize, const N: usize>(mut board: Board<ORDER, N>) -> Option<Board<ORDER, N>> {
+    if board.has_conflict() {
+        return None;
+    }
+
+    loop {
+        let mut changed = false;
+
+        for y in 0..N {
+            for x in 0..N {
+                if let Cell::Open = board.grid[y][x] {
+                    let candidates = board.get_possible(x, y);
+                    match candidates.len() {
+                        0 => return None,
+                        1 => {
+                            board = board.patch(x, y, Cell::Fixed(candidates[0]));
+                            changed = true;
+                        },
+                        _ => {},
+                    }
                 }
             }
+        }
 
-            None
-        },
+        if let Some((x, y, num)) = board.find_hidden_single() {
+            board = board.patch(x, y, Cell::Fixed(num));
+            changed = true;
+        }
+
+        if !changed {
+            break;
+        }
     }
+
+    Some(board)
 }
 
-/// Increments a pair of coordinates.
-/// Adds 1 to the x, setting it back to 0 and incrementing
-/// y after x reaches 8
-fn next_coords(x: usize, y: usize) -> (usize, usize) {
-    let mut y = y;
-    let x = match x {
-        8 => {
-            y = y + 1;
-            0
-        },
-        _ => x + 1
-    };
-    
-    (x, y)
+/// Builds the two main diagonals as extra units, turning a standard
+/// board into an X-Sudoku.
+pub fn diagonal_units<const N: usize>() -> Vec<Unit> {
+    vec![
+        (0..N).map(|i| (i, i)).collect(),
+        (0..N).map(|i| (N - 1 - i, i)).collect(),
+    ]
 }
 
+/// Builds the four extra 3x3 "windows" used by hyper-sudoku, each
+/// offset one cell in from the nearest box boundary. Only meaningful
+/// for a standard (`ORDER = 3`) 9x9 board.
+pub fn hyper_units() -> Vec<Unit> {
+    let mut units = Vec::with_capacity(4);
+
+    for &(box_x, box_y) in &[(1, 1), (5, 1), (1, 5), (5, 5)] {
+        let mut unit = Vec::with_capacity(9);
+        for y in 0..3 {
+            for x in 0..3 {
+                unit.push((box_x + x, box_y + y));
+            }
+        }
+        units.push(unit);
+    }
+
+    units
+}
 
 #[cfg(test)]
 use std::io::BufReader;
@@ -204,7 +492,7 @@ use std::io::BufReader;
 #[test]
 fn test_patch() {
     let lines = BufReader::new("1\n\n\n\n\n\n\n\n\n".as_bytes()).lines();
-    let board = Board::parse_puzzle(lines)
+    let board = Board::<3, 9>::parse_puzzle(lines)
         .unwrap()
         .0
         .patch(1, 0, Cell::Fixed(2));
@@ -213,6 +501,30 @@ fn test_patch() {
     assert_eq!(Cell::Fixed(2), board.grid[0][1]);
 }
 
+#[test]
+fn test_parse_puzzle_compact_single_line() {
+    let mut puzzle_str = String::from("123456789");
+    puzzle_str.push_str(&".".repeat(81 - 9));
+
+    let lines = BufReader::new(puzzle_str.as_bytes()).lines();
+    let board = Board::<3, 9>::parse_puzzle(lines).unwrap().0;
+
+    assert_eq!(Cell::Fixed(5), board.grid[0][4]);
+    assert_eq!(Cell::Open, board.grid[1][0]);
+}
+
+#[test]
+fn test_to_line_string_round_trips() {
+    let lines = BufReader::new("1\n\n\n\n\n\n\n\n\n".as_bytes()).lines();
+    let board = Board::<3, 9>::parse_puzzle(lines).unwrap().0;
+    let line = board.to_line_string();
+
+    let round_trip_lines = BufReader::new(line.as_bytes()).lines();
+    let round_trip = Board::<3, 9>::parse_puzzle(round_trip_lines).unwrap().0;
+
+    assert_eq!(board.to_line_string(), round_trip.to_line_string());
+}
+
 #[test]
 fn test_parse_puzzle() {
     let puzzle_str =
@@ -228,31 +540,157 @@ fn test_parse_puzzle() {
 ";
 
     let lines = BufReader::new(puzzle_str.as_bytes()).lines();
-    
-    let puzzle = Board::parse_puzzle(lines);
+
+    let puzzle = Board::<3, 9>::parse_puzzle(lines);
     assert_eq!(puzzle_str, puzzle.unwrap().0.to_string());
 }
 
 #[test]
-fn test_coords() {
-    assert_eq!((1, 0), next_coords(0, 0));
-    assert_eq!((0, 1), next_coords(8, 0));
+fn test_select_cell_picks_fewest_candidates() {
+    let puzzle_str =
+"12345678
+
+
+
+
+
+
+
+
+";
+
+    let lines = BufReader::new(puzzle_str.as_bytes()).lines();
+    let board = Board::<3, 9>::parse_puzzle(lines).unwrap().0;
+
+    assert_eq!(Some((8, 0)), board.select_cell());
 }
 
 #[test]
 fn test_get_possible() {
     let lines = BufReader::new(" 23456789
-2        
-3        
-4        
-5        
-6        
-7        
-8        
+2
+3
+4
+5
+6
+7
+8
 9        ".as_bytes()).lines();
 
-    let board = Board::parse_puzzle(lines).unwrap().0;
+    let board = Board::<3, 9>::parse_puzzle(lines).unwrap().0;
+
+    assert_eq!(vec![1], board.get_possible(0, 0));
+}
+
+#[test]
+fn test_propagate_fills_naked_single() {
+    let puzzle_str =
+"12345678
+
+
+
+
+
+
+
+
+";
+
+    let lines = BufReader::new(puzzle_str.as_bytes()).lines();
+    let board = Board::<3, 9>::parse_puzzle(lines).unwrap().0;
+    let solved = backtrack(board).unwrap();
+
+    assert_eq!(Cell::Fixed(9), solved.grid[0][8]);
+}
+
+#[test]
+fn test_find_hidden_single_in_detects_value_forced_to_one_cell() {
+    // Within box 0, every open cell still has several candidates (no
+    // naked single applies anywhere), but 9 has been ruled out of all
+    // of them except (2, 2) by givens in other rows/columns/boxes, so
+    // only the hidden-single rule can pin it down.
+    let puzzle_str =
+"12  9
+  3
+4
+
+
+9
+
+
+ 9
+
+";
+
+    let lines = BufReader::new(puzzle_str.as_bytes()).lines();
+    let board = Board::<3, 9>::parse_puzzle(lines).unwrap().0;
+
+    assert!(board.get_possible(2, 2).len() > 1);
+
+    let box0: Vec<(usize, usize)> = vec![
+        (0, 0), (1, 0), (2, 0),
+        (0, 1), (1, 1), (2, 1),
+        (0, 2), (1, 2), (2, 2),
+    ];
+    assert_eq!(Some((2, 2, 9)), board.find_hidden_single_in(&box0));
+}
+
+#[test]
+fn test_solves_4x4() {
+    let puzzle_str =
+"12
+
+
+   3
+";
+
+    let lines = BufReader::new(puzzle_str.as_bytes()).lines();
+    let board = Board::<2, 4>::parse_puzzle(lines).unwrap().0;
+    let solved = backtrack(board);
+
+    assert!(solved.is_some());
+}
+
+#[test]
+fn test_count_solutions_detects_multiple_and_none() {
+    let lines = BufReader::new("\n\n\n\n".as_bytes()).lines();
+    let board = Board::<2, 4>::parse_puzzle(lines).unwrap().0;
+    assert_eq!(2, count_solutions(board, 2));
+
+    let puzzle_str =
+"1234
+3
+ 4
+
+";
+
+    let lines = BufReader::new(puzzle_str.as_bytes()).lines();
+    let board = Board::<2, 4>::parse_puzzle(lines).unwrap().0;
+    assert_eq!(0, count_solutions(board, 2));
+}
+
+#[test]
+fn test_count_solutions_detects_conflicting_givens() {
+    // Two identical givens in the same row is an immediate
+    // contradiction, which neither naked nor hidden singles would
+    // ever notice on their own; this must resolve instantly rather
+    // than exhaust a near-blank search tree.
+    let puzzle_str = "11       \n\n\n\n\n\n\n\n\n";
+
+    let lines = BufReader::new(puzzle_str.as_bytes()).lines();
+    let board = Board::<3, 9>::parse_puzzle(lines).unwrap().0;
+    assert_eq!(0, count_solutions(board, 2));
+}
+
+#[test]
+fn test_diagonal_variant_constrains_diagonal_cells() {
+    // (4, 4) shares no row, column or box with (0, 0), so only the
+    // main diagonal unit can constrain it.
+    let lines = BufReader::new("5\n\n\n\n\n\n\n\n\n".as_bytes()).lines();
+    let board = Board::<3, 9>::parse_puzzle(lines).unwrap().0;
+
+    assert!(board.get_possible(4, 4).contains(&5));
 
-    let expected: HashSet<u8> = vec![1].into_iter().collect();
-    assert_eq!(expected, board.get_possible(0, 0));
+    let board = board.with_extra_units(diagonal_units::<9>());
+    assert!(!board.get_possible(4, 4).contains(&5));
 }